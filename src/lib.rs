@@ -23,9 +23,26 @@
 //!   val.safe_pow(8_u32)?;
 //! }
 //! ```
+//!
+//! For long arithmetic chains, `SafeNum` wraps a value together with a sticky
+//! error state so the `?` only needs to happen once, at the end of the chain.
+//!
+//! ```
+//! use solana_safe_math::SafeNum;
+//! use std::convert::TryFrom;
+//!
+//! fn process_init_escrow(amount: u64) -> Result<u64, solana_program::program_error::ProgramError> {
+//!   let val = 10_u64;
+//!
+//!   let r = (SafeNum::from(val) * amount) / amount + val;
+//!   u64::try_from(r)
+//! }
+//! ```
 use solana_program::program_error::ProgramError;
 use thiserror::Error;
 use std::{
+  convert::TryFrom,
+  ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
   result::Result as StdResult
 };
 
@@ -105,3 +122,189 @@ safe_math!(u64);
 safe_math!(u32);
 safe_math!(u16);
 safe_math!(u8);
+
+/// A numeric value paired with a sticky error state, enabling fluent chained
+/// arithmetic such as `(SafeNum::from(a) * b) / c` without a `?` after every
+/// step. Once an operation overflows, underflows or divides by zero the
+/// poison flag is set and every subsequent operation is a no-op that keeps
+/// the first error. The result is only observed once, via `TryFrom`.
+#[derive(Debug, Copy, Clone)]
+pub struct SafeNum<T> {
+  value: T,
+  error: Option<ErrorCode>,
+}
+
+impl<T> SafeNum<T> {
+  /// Returns `true` if a previous operation has poisoned this value.
+  pub fn has_error(&self) -> bool {
+    self.error.is_some()
+  }
+}
+
+impl<T> From<T> for SafeNum<T> {
+  fn from(value: T) -> Self {
+    Self { value, error: None }
+  }
+}
+
+macro_rules! safe_num {
+  ($type: ident) => {
+    impl Add<$type> for SafeNum<$type> {
+      type Output = SafeNum<$type>;
+
+      fn add(self, rhs: $type) -> Self::Output {
+        if let Some(error) = self.error {
+          return SafeNum { value: self.value, error: Some(error) };
+        }
+
+        match self.value.checked_add(rhs) {
+          Some(value) => SafeNum { value, error: None },
+          None => SafeNum { value: self.value, error: Some(ErrorCode::Overflow) }
+        }
+      }
+    }
+
+    impl AddAssign<$type> for SafeNum<$type> {
+      fn add_assign(&mut self, rhs: $type) {
+        *self = *self + rhs;
+      }
+    }
+
+    impl Sub<$type> for SafeNum<$type> {
+      type Output = SafeNum<$type>;
+
+      fn sub(self, rhs: $type) -> Self::Output {
+        if let Some(error) = self.error {
+          return SafeNum { value: self.value, error: Some(error) };
+        }
+
+        match self.value.checked_sub(rhs) {
+          Some(value) => SafeNum { value, error: None },
+          None => SafeNum { value: self.value, error: Some(ErrorCode::Underflow) }
+        }
+      }
+    }
+
+    impl SubAssign<$type> for SafeNum<$type> {
+      fn sub_assign(&mut self, rhs: $type) {
+        *self = *self - rhs;
+      }
+    }
+
+    impl Mul<$type> for SafeNum<$type> {
+      type Output = SafeNum<$type>;
+
+      fn mul(self, rhs: $type) -> Self::Output {
+        if let Some(error) = self.error {
+          return SafeNum { value: self.value, error: Some(error) };
+        }
+
+        match self.value.checked_mul(rhs) {
+          Some(value) => SafeNum { value, error: None },
+          None => SafeNum { value: self.value, error: Some(ErrorCode::Overflow) }
+        }
+      }
+    }
+
+    impl MulAssign<$type> for SafeNum<$type> {
+      fn mul_assign(&mut self, rhs: $type) {
+        *self = *self * rhs;
+      }
+    }
+
+    impl Div<$type> for SafeNum<$type> {
+      type Output = SafeNum<$type>;
+
+      fn div(self, rhs: $type) -> Self::Output {
+        if let Some(error) = self.error {
+          return SafeNum { value: self.value, error: Some(error) };
+        }
+
+        match self.value.checked_div(rhs) {
+          Some(value) => SafeNum { value, error: None },
+          None => SafeNum { value: self.value, error: Some(ErrorCode::DivisionByZero) }
+        }
+      }
+    }
+
+    impl DivAssign<$type> for SafeNum<$type> {
+      fn div_assign(&mut self, rhs: $type) {
+        *self = *self / rhs;
+      }
+    }
+
+    impl TryFrom<SafeNum<$type>> for $type {
+      type Error = ProgramError;
+
+      fn try_from(num: SafeNum<$type>) -> StdResult<Self, Self::Error> {
+        match num.error {
+          Some(error) => Err(error.into()),
+          None => Ok(num.value)
+        }
+      }
+    }
+  }
+}
+
+safe_num!(u128);
+safe_num!(u64);
+safe_num!(u32);
+safe_num!(u16);
+safe_num!(u8);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn safe_num_chains_without_error() {
+    let r = (SafeNum::from(10_u64) * 4_u64) / 2_u64 + 1_u64;
+
+    assert_eq!(u64::try_from(r), Ok(21_u64));
+  }
+
+  #[test]
+  fn safe_num_poisons_on_overflow() {
+    let r = SafeNum::from(u64::MAX) + 1_u64;
+
+    assert!(r.has_error());
+    assert_eq!(u64::try_from(r), Err(ErrorCode::Overflow.into()));
+  }
+
+  #[test]
+  fn safe_num_poisons_on_underflow() {
+    let r = SafeNum::from(0_u64) - 1_u64;
+
+    assert!(r.has_error());
+    assert_eq!(u64::try_from(r), Err(ErrorCode::Underflow.into()));
+  }
+
+  #[test]
+  fn safe_num_poisons_on_division_by_zero() {
+    let r = SafeNum::from(1_u64) / 0_u64;
+
+    assert!(r.has_error());
+    assert_eq!(u64::try_from(r), Err(ErrorCode::DivisionByZero.into()));
+  }
+
+  #[test]
+  fn safe_num_keeps_first_error_once_poisoned() {
+    // Poison via overflow, then apply an op that would otherwise fail with
+    // a different error (division by zero) - the first error must stick.
+    let r = (SafeNum::from(u64::MAX) + 1_u64) / 0_u64;
+
+    assert!(r.has_error());
+    assert_eq!(u64::try_from(r), Err(ErrorCode::Overflow.into()));
+  }
+
+  #[test]
+  fn safe_num_assign_ops_match_non_assign_ops() {
+    let mut r = SafeNum::from(10_u64);
+    r += 5_u64;
+    r -= 3_u64;
+    r *= 2_u64;
+    r /= 4_u64;
+
+    assert_eq!(u64::try_from(r), Ok(6_u64));
+  }
+}